@@ -0,0 +1,29 @@
+// bitvm2_solar_insurance_demo_main.rs
+
+// The command-line front end: parses the arguments and hands them to the
+// library in `bitvm2_solar_insurance_demo.rs`.
+extern crate bitvm2_solar_insurance_demo as demo;
+
+use std::io;
+
+fn main() {
+    let mut raw_args = std::env::args().skip(1).peekable();
+    let status = if raw_args.peek().map(String::as_str) == Some("verify") {
+        match (raw_args.nth(1), raw_args.next()) {
+            (Some(path), None) => demo::run_verify_command(&path, &mut io::stdout()),
+            _ => {
+                eprintln!("error: verify takes exactly one proof file\n{}", demo::USAGE);
+                2
+            }
+        }
+    } else {
+        match demo::parse_args(raw_args) {
+            Ok(args) => demo::run(&args),
+            Err(err) => {
+                eprintln!("error: {}\n{}", err, demo::USAGE);
+                2
+            }
+        }
+    };
+    std::process::exit(status);
+}
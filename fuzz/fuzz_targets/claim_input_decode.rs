@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The demo is a single rustc-built file, so the target compiles it in as a module;
+// its own lints are checked where it is built standalone. Only its fuzzing entry
+// points are used here, so the rest of it is dead code in this crate.
+#[allow(dead_code, clippy::all)]
+#[path = "../../bitvm2_solar_insurance_demo.rs"]
+mod demo;
+
+fuzz_target!(|data: &[u8]| {
+    demo::fuzzing::claim_input_decode(data);
+});
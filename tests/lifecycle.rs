@@ -0,0 +1,109 @@
+//! End-to-end claim lifecycle across prover honesty, damage and policy term.
+//! Built into the demo's test binary (see the `#[path]` module in the demo).
+
+use super::*;
+
+/// Payout the contract owes on the default 5,000,000 sat hail policy.
+#[derive(Debug, Clone, Copy)]
+struct Damage {
+    severity: u8,
+    efficiency_loss_pct: u8,
+    owed: u64,
+}
+
+const NO_DAMAGE: Damage = Damage { severity: 0, efficiency_loss_pct: 0, owed: 0 };
+const MID_SEVERITY: Damage = Damage { severity: 5, efficiency_loss_pct: 50, owed: 2_500_000 };
+const MAX_SEVERITY: Damage = Damage { severity: 10, efficiency_loss_pct: 100, owed: 5_000_000 };
+
+const COVERAGE: u64 = 5_000_000;
+const PREMIUM: u64 = 250_000;
+
+fn run(mode: ProverMode, damage: Damage, claim_height: u32) -> Result<LifecycleResult, LifecycleError> {
+    let scenario = Scenario {
+        severity: damage.severity,
+        efficiency_loss_pct: damage.efficiency_loss_pct,
+        prover_mode: mode,
+        claim_height,
+        ..Scenario::default()
+    };
+    run_claim_lifecycle(&DemoConfig { animation_speed: AnimationSpeed::Instant, scenario, ..Default::default() })
+}
+
+/// The events every claim records up to verification.
+fn claim_events(damage: Damage, committed: u64, valid: bool) -> Vec<AuditEvent> {
+    vec![
+        AuditEvent::FundsLocked { seq: 0, amount: COVERAGE },
+        AuditEvent::PremiumCollected { seq: 1, amount: PREMIUM },
+        AuditEvent::ClaimReceived { seq: 2, coverage: COVERAGE, severity: damage.severity },
+        AuditEvent::ProofGenerated { seq: 3, payout: committed, nonce: 0 },
+        AuditEvent::ProofVerified { seq: 4, valid },
+    ]
+}
+
+#[test]
+fn test_honest_claims_within_term_pay_what_is_owed() {
+    for damage in [NO_DAMAGE, MID_SEVERITY, MAX_SEVERITY] {
+        let result = run(ProverMode::Honest, damage, DEMO_CLAIM_HEIGHT).unwrap();
+        assert!(result.verified, "{:?}", damage);
+        assert_eq!(result.payout, damage.owed, "{:?}", damage);
+        assert_eq!(result.settled, damage.owed > 0, "{:?}", damage);
+        let mut expected = claim_events(damage, damage.owed, true);
+        if let Some(spend) = &result.spend {
+            expected.push(AuditEvent::PayoutSent { seq: 5, amount: spend.to_policyholder.as_u64() });
+        }
+        assert_eq!(result.audit, expected, "{:?}", damage);
+        assert_eq!(result.spend.is_some(), damage.owed > 0, "{:?}", damage);
+        assert!(result.settlement.as_ref().is_none_or(SettlementReport::reconciles), "{:?}", damage);
+    }
+}
+
+#[test]
+fn test_full_coverage_payout_takes_fee_from_policyholder() {
+    let spend = run(ProverMode::Honest, MAX_SEVERITY, DEMO_CLAIM_HEIGHT).unwrap().spend.unwrap();
+    assert_eq!(spend.to_policyholder, U256::from(COVERAGE - PAYOUT_FEE_SATS));
+    assert_eq!(spend.residual_in_contract, U256::ZERO);
+}
+
+#[test]
+fn test_fraudulent_claims_within_term_are_rejected() {
+    for damage in [NO_DAMAGE, MID_SEVERITY] {
+        let result = run(ProverMode::Dishonest, damage, DEMO_CLAIM_HEIGHT).unwrap();
+        assert!(!result.verified, "{:?}", damage);
+        assert_eq!(result.payout, 0, "{:?}", damage);
+        assert!(!result.settled, "{:?}", damage);
+        assert_eq!(result.audit, claim_events(damage, COVERAGE, false), "{:?}", damage);
+        assert_eq!(result.ledger.pool_locked, sats(COVERAGE), "{:?}", damage);
+    }
+    // Claiming the full coverage is honest when the damage is total
+    let result = run(ProverMode::Dishonest, MAX_SEVERITY, DEMO_CLAIM_HEIGHT).unwrap();
+    assert!(result.verified && result.settled);
+    assert_eq!(result.payout, MAX_SEVERITY.owed);
+}
+
+#[test]
+fn test_expired_claims_are_never_proven() {
+    let claim_height = POLICY_TERM.expiry_height() + 1;
+    let expired = ContractError::PolicyExpired { expiry_height: POLICY_TERM.expiry_height(), current_height: claim_height };
+    for mode in [ProverMode::Honest, ProverMode::Dishonest] {
+        for damage in [NO_DAMAGE, MID_SEVERITY, MAX_SEVERITY] {
+            assert_eq!(
+                run(mode, damage, claim_height).err(),
+                Some(LifecycleError::Protocol(ProtocolError::MalformedInput(expired.to_string()))),
+                "{:?} {:?}",
+                mode,
+                damage
+            );
+        }
+    }
+}
+
+#[test]
+fn test_claim_on_expiry_height_is_still_covered() {
+    let result = run(ProverMode::Honest, MID_SEVERITY, POLICY_TERM.expiry_height()).unwrap();
+    assert!(result.verified && result.settled);
+    assert_eq!(result.payout, MID_SEVERITY.owed);
+}
+
+fn sats(amount: u64) -> Satoshi {
+    Satoshi::from_sat(amount)
+}